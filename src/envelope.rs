@@ -24,10 +24,16 @@ pub struct ADSR {
     current_amplitude: f32,
     /// Velocity of the note (0.0 to 1.0).
     velocity: f32,
+    /// Power-law curvature applied to the attack/decay/release ramps. `1.0` is linear; larger
+    /// values give the decay and release ramps a fast-then-slow exponential shape, while the
+    /// attack ramp (`p.powf(curve)`) starts slowly and speeds up.
+    curve: f32,
+    /// Whether `on()` has ever been called on this envelope. It is not cleared after release.
+    triggered: bool,
 }
 
 /// Struct for updating ADSR parameters.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub struct ADSRUpdate {
     /// New attack time (if provided).
     pub attack: Option<f32>,
@@ -37,6 +43,8 @@ pub struct ADSRUpdate {
     pub sustain: Option<f32>,
     /// New release time (if provided).
     pub release: Option<f32>,
+    /// New curve amount (if provided).
+    pub curve: Option<f32>,
 }
 
 impl ADSR {
@@ -65,6 +73,8 @@ impl ADSR {
             note_off_sample: None,
             current_amplitude: 0.0,
             velocity: 1.0,
+            curve: 1.0,
+            triggered: false,
         }
     }
 
@@ -86,6 +96,9 @@ impl ADSR {
         if let Some(release) = params.release {
             self.release = release.max(0.0);
         }
+        if let Some(curve) = params.curve {
+            self.curve = curve.max(0.01);
+        }
     }
 
     /// Triggers the envelope with a given velocity.
@@ -97,6 +110,7 @@ impl ADSR {
         self.trigger_sample = self.current_sample;
         self.note_off_sample = None;
         self.velocity = velocity.clamp(0.0, 1.0);
+        self.triggered = true;
     }
 
     /// Releases the envelope, starting the release phase.
@@ -131,17 +145,19 @@ impl ADSR {
                 if release_time >= self.release {
                     0.0
                 } else {
-                    self.sustain * (1.0 - release_time / self.release)
+                    let p = release_time / self.release;
+                    self.sustain * (1.0 - p).powf(self.curve)
                 }
             }
             _ => {
                 // Attack, Decay, or Sustain phase
                 if time < attack_end {
                     // Attack
-                    time / attack_end
+                    (time / attack_end).powf(self.curve)
                 } else if time < decay_end {
                     // Decay
-                    let decay_progress = (time - attack_end) / self.decay;
+                    let p = (time - attack_end) / self.decay;
+                    let decay_progress = 1.0 - (1.0 - p).powf(self.curve);
                     1.0 - (1.0 - self.sustain) * decay_progress
                 } else {
                     // Sustain
@@ -155,13 +171,30 @@ impl ADSR {
         self.current_amplitude
     }
 
-    /// Checks if the envelope is still active (non-zero amplitude).
+    /// Checks if the envelope is still active, i.e. still in its attack/decay/sustain phase, or
+    /// in its release phase but not yet done releasing.
+    ///
+    /// This is phase-based rather than amplitude-based: right after `on()` the amplitude is
+    /// still `0.0` (the attack ramps up from silence), so checking `current_amplitude > 0.0`
+    /// would report a just-triggered envelope as inactive before it ever gets to produce sound.
     ///
     /// # Returns
     ///
-    /// `true` if the envelope is still producing non-zero amplitude, `false` otherwise
+    /// `true` if the envelope is still in its attack/decay/sustain/release phases, `false` once
+    /// it has fully released (or was never triggered)
     pub fn is_active(&self) -> bool {
-        self.current_amplitude > 0.0
+        if !self.triggered {
+            return false;
+        }
+
+        match self.note_off_sample {
+            Some(note_off) => {
+                let release_time =
+                    (self.current_sample.saturating_sub(note_off)) as f32 / self.sample_rate;
+                release_time < self.release
+            }
+            None => true,
+        }
     }
 }
 
@@ -179,6 +212,8 @@ impl Default for ADSR {
             note_off_sample: None,
             current_amplitude: 0.0,
             velocity: 1.0,
+            curve: 1.0,
+            triggered: false,
         }
     }
 }
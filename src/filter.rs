@@ -0,0 +1,76 @@
+//! This module implements a resonant state-variable filter (SVF) using the Chamberlin
+//! topology, giving simultaneous low-pass, high-pass, band-pass, and notch outputs from a
+//! single pair of state variables.
+
+use std::f32::consts::PI;
+
+use nih_plug::prelude::Enum;
+
+/// Selects which of the SVF's simultaneous outputs is passed through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum FilterMode {
+    /// Attenuates frequencies above the cutoff.
+    LowPass,
+    /// Attenuates frequencies below the cutoff.
+    HighPass,
+    /// Passes only frequencies near the cutoff.
+    BandPass,
+    /// Attenuates only frequencies near the cutoff.
+    Notch,
+}
+
+/// A Chamberlin state-variable filter.
+///
+/// Keeps its own `low`/`band` state so a separate instance can be used per voice.
+pub struct StateVariableFilter {
+    /// The sample rate of the audio system.
+    sample_rate: f32,
+    /// Low-pass state variable.
+    low: f32,
+    /// Band-pass state variable.
+    band: f32,
+}
+
+impl StateVariableFilter {
+    /// Creates a new, silent state-variable filter.
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            sample_rate,
+            low: 0.0,
+            band: 0.0,
+        }
+    }
+
+    /// Resets the filter's state variables, e.g. when its voice is retriggered.
+    pub fn reset(&mut self) {
+        self.low = 0.0;
+        self.band = 0.0;
+    }
+
+    /// Filters `input`, returning the output selected by `mode`.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input sample
+    /// * `cutoff` - The cutoff frequency in Hz, clamped internally to stay stable
+    /// * `resonance` - The filter resonance, larger values giving a narrower, more resonant peak
+    /// * `mode` - Which of the SVF's simultaneous outputs to return
+    pub fn process(&mut self, input: f32, cutoff: f32, resonance: f32, mode: FilterMode) -> f32 {
+        // Clamp the cutoff to sample_rate / 6, which caps `f` at 1.0 so the filter stays stable.
+        let cutoff = cutoff.min(self.sample_rate / 6.0);
+        let f = 2.0 * (PI * cutoff / self.sample_rate).sin();
+        let q = 1.0 / resonance.max(0.01);
+
+        self.low += f * self.band;
+        let high = input - self.low - q * self.band;
+        self.band += f * high;
+        let notch = high + self.low;
+
+        match mode {
+            FilterMode::LowPass => self.low,
+            FilterMode::HighPass => high,
+            FilterMode::BandPass => self.band,
+            FilterMode::Notch => notch,
+        }
+    }
+}
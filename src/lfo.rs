@@ -0,0 +1,64 @@
+//! This module implements a low-frequency oscillator (LFO) for modulating volume (tremolo)
+//! or pitch (vibrato). Unlike the audio-rate [`crate::oscillator::PolyBlepOscillator`], an LFO
+//! runs at sub-audio rates and needs no anti-aliasing, so it samples the shared sine lookup
+//! table directly.
+
+use nih_plug::prelude::Enum;
+
+use crate::oscillator::fast_sin;
+
+/// Selects what an [`Lfo`]'s output modulates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum LfoTarget {
+    /// The LFO does not modulate anything.
+    Off,
+    /// The LFO modulates output volume.
+    Tremolo,
+    /// The LFO modulates oscillator pitch.
+    Vibrato,
+}
+
+/// A simple sine low-frequency oscillator.
+pub struct Lfo {
+    /// The current phase of the LFO (0.0 to 1.0).
+    phase: f32,
+    /// The amount to increment the phase each sample.
+    phase_increment: f32,
+}
+
+impl Lfo {
+    /// Creates a new LFO.
+    ///
+    /// # Arguments
+    ///
+    /// * `sample_rate` - The sample rate of the audio system
+    /// * `frequency` - The initial LFO rate in Hz
+    pub fn new(sample_rate: f32, frequency: f32) -> Self {
+        let mut lfo = Self {
+            phase: 0.0,
+            phase_increment: 0.0,
+        };
+        lfo.set_frequency(sample_rate, frequency);
+        lfo
+    }
+
+    /// Sets the LFO's rate.
+    ///
+    /// # Arguments
+    ///
+    /// * `sample_rate` - The sample rate of the audio system
+    /// * `frequency` - The new LFO rate in Hz
+    pub fn set_frequency(&mut self, sample_rate: f32, frequency: f32) {
+        self.phase_increment = frequency / sample_rate;
+    }
+
+    /// Generates the next sample of the LFO, in the range `[-1.0, 1.0]`.
+    pub fn next_sample(&mut self) -> f32 {
+        let sample = fast_sin(self.phase);
+
+        self.phase += self.phase_increment;
+        self.phase -= self.phase.floor();
+
+        sample
+    }
+}
@@ -1,9 +1,13 @@
 //! PawWave Synthesizer Plugin
 //!
-//! This module implements a simple yet versatile monophonic synthesizer plugin called PawWave.
+//! This module implements a simple yet versatile polyphonic synthesizer plugin called PawWave.
 //! It features:
 //! - A PolyBLEP oscillator with multiple waveform options (Sine, Square, Saw, Triangle)
 //! - An ADSR (Attack, Decay, Sustain, Release) envelope
+//! - A resonant state-variable filter with low-pass, high-pass, band-pass, and notch modes
+//! - Phase-modulation (FM) between a sine modulator and the carrier oscillator
+//! - A wavetable-backed LFO for tremolo (volume) or vibrato (pitch) modulation
+//! - A fixed-size voice pool so overlapping MIDI notes sound simultaneously
 //! - Volume control with dB scaling
 //! - MIDI input for note events
 //! - Support for both CLAP and VST3 plugin formats
@@ -15,18 +19,23 @@ use nih_plug::prelude::*;
 use std::sync::Arc;
 
 mod envelope;
+mod filter;
+mod lfo;
 mod oscillator;
+mod voice;
 
-use envelope::ADSR;
-use oscillator::{OscillatorType, PolyBlepOscillator};
+use envelope::ADSRUpdate;
+use filter::FilterMode;
+use lfo::{Lfo, LfoTarget};
+use oscillator::OscillatorType;
+use voice::{VoiceManager, MAX_VOICES};
 
 // Main struct for the PawWave synthesizer
 struct PawWave {
     params: Arc<PawWaveParams>,
     sample_rate: f32,
-    osc: PolyBlepOscillator,
-    adsr: ADSR,
-    gain: Smoother<f32>,
+    voices: VoiceManager,
+    lfo: Lfo,
 }
 
 // Parameters for the PawWave synthesizer
@@ -37,6 +46,39 @@ struct PawWaveParams {
 
     #[id = "waveform"]
     pub waveform: EnumParam<OscillatorType>,
+
+    #[id = "polyphony"]
+    pub polyphony: IntParam,
+
+    #[id = "filter_cutoff"]
+    pub filter_cutoff: FloatParam,
+
+    #[id = "filter_resonance"]
+    pub filter_resonance: FloatParam,
+
+    #[id = "filter_mode"]
+    pub filter_mode: EnumParam<FilterMode>,
+
+    #[id = "pulse_width"]
+    pub pulse_width: FloatParam,
+
+    #[id = "fm_ratio"]
+    pub fm_ratio: FloatParam,
+
+    #[id = "fm_depth"]
+    pub fm_depth: FloatParam,
+
+    #[id = "envelope_curve"]
+    pub envelope_curve: FloatParam,
+
+    #[id = "lfo_target"]
+    pub lfo_target: EnumParam<LfoTarget>,
+
+    #[id = "lfo_rate"]
+    pub lfo_rate: FloatParam,
+
+    #[id = "lfo_depth"]
+    pub lfo_depth: FloatParam,
 }
 
 impl Default for PawWave {
@@ -44,9 +86,8 @@ impl Default for PawWave {
         Self {
             params: Arc::new(PawWaveParams::default()),
             sample_rate: 44100.0,
-            osc: PolyBlepOscillator::new(44100.0, 440.0), // Default to 440 Hz (A4)
-            adsr: ADSR::default(),
-            gain: Smoother::new(SmoothingStyle::Linear(5.0)),
+            voices: VoiceManager::new(44100.0),
+            lfo: Lfo::new(44100.0, 5.0),
         }
     }
 }
@@ -71,6 +112,118 @@ impl Default for PawWaveParams {
 
             // Waveform selection parameter
             waveform: EnumParam::new("Waveform", OscillatorType::Sine),
+
+            // Maximum number of notes that can sound at once
+            polyphony: IntParam::new(
+                "Polyphony",
+                8,
+                IntRange::Linear {
+                    min: 1,
+                    max: MAX_VOICES as i32,
+                },
+            ),
+
+            // Filter cutoff, skewed so low frequencies get more knob travel
+            filter_cutoff: FloatParam::new(
+                "Filter Cutoff",
+                20_000.0,
+                FloatRange::Skewed {
+                    min: 20.0,
+                    max: 20_000.0,
+                    factor: FloatRange::skew_factor(-2.0),
+                },
+            )
+            .with_smoother(SmoothingStyle::Logarithmic(50.0))
+            .with_unit(" Hz")
+            .with_value_to_string(formatters::v2s_f32_hz_then_khz(0))
+            .with_string_to_value(formatters::s2v_f32_hz_then_khz()),
+
+            // Filter resonance
+            filter_resonance: FloatParam::new(
+                "Filter Resonance",
+                0.5,
+                FloatRange::Linear {
+                    min: 0.5,
+                    max: 10.0,
+                },
+            )
+            .with_smoother(SmoothingStyle::Linear(50.0)),
+
+            // Filter mode selection
+            filter_mode: EnumParam::new("Filter Mode", FilterMode::LowPass),
+
+            // Square wave duty cycle, also modulatable for classic PWM sweeps
+            pulse_width: FloatParam::new(
+                "Pulse Width",
+                0.5,
+                FloatRange::Linear {
+                    min: 0.01,
+                    max: 0.99,
+                },
+            )
+            .with_smoother(SmoothingStyle::Linear(10.0)),
+
+            // FM modulator frequency, expressed as a ratio of the carrier's note frequency
+            fm_ratio: FloatParam::new(
+                "FM Ratio",
+                1.0,
+                FloatRange::Linear {
+                    min: 0.5,
+                    max: 16.0,
+                },
+            )
+            .with_smoother(SmoothingStyle::Linear(10.0)),
+
+            // FM modulation depth, in cycles of phase offset applied to the carrier
+            fm_depth: FloatParam::new(
+                "FM Depth",
+                0.0,
+                FloatRange::Linear {
+                    min: 0.0,
+                    max: 8.0,
+                },
+            )
+            .with_smoother(SmoothingStyle::Linear(10.0)),
+
+            // Envelope segment curvature: 1.0 is linear, higher values make decay and
+            // release fall fast-then-slow and make the attack rise slow-then-fast
+            envelope_curve: FloatParam::new(
+                "Envelope Curve",
+                1.0,
+                FloatRange::Skewed {
+                    min: 0.1,
+                    max: 10.0,
+                    factor: FloatRange::skew_factor(-1.0),
+                },
+            )
+            .with_smoother(SmoothingStyle::Linear(10.0)),
+
+            // LFO modulation target
+            lfo_target: EnumParam::new("LFO Target", LfoTarget::Off),
+
+            // LFO rate
+            lfo_rate: FloatParam::new(
+                "LFO Rate",
+                5.0,
+                FloatRange::Skewed {
+                    min: 0.1,
+                    max: 20.0,
+                    factor: FloatRange::skew_factor(-1.0),
+                },
+            )
+            .with_unit(" Hz")
+            .with_smoother(SmoothingStyle::Linear(10.0)),
+
+            // LFO modulation depth
+            lfo_depth: FloatParam::new(
+                "LFO Depth",
+                0.0,
+                FloatRange::Linear {
+                    min: 0.0,
+                    max: 1.0,
+                },
+            )
+            .with_smoother(SmoothingStyle::Linear(10.0)),
         }
     }
 }
@@ -120,8 +273,8 @@ impl Plugin for PawWave {
 
         // Initialize components with the correct sample rate
         self.sample_rate = sample_rate;
-        self.osc = PolyBlepOscillator::new(sample_rate, 440.0);
-        self.adsr = ADSR::new(0.02, 0.02, 0.5, 0.5, sample_rate);
+        self.voices.reset(sample_rate);
+        self.lfo = Lfo::new(sample_rate, self.params.lfo_rate.value());
 
         true
     }
@@ -137,6 +290,7 @@ impl Plugin for PawWave {
         context: &mut impl ProcessContext<Self>,
     ) -> ProcessStatus {
         let mut next_event = context.next_event();
+        let max_polyphony = self.params.polyphony.value() as usize;
 
         for (sample_id, channel_samples) in buffer.iter_samples().enumerate() {
             // Process all MIDI events for this sample
@@ -147,11 +301,15 @@ impl Plugin for PawWave {
 
                 match event {
                     NoteEvent::NoteOn { note, velocity, .. } => {
-                        self.osc.set_frequency(util::midi_note_to_freq(note));
-                        self.adsr.on(velocity);
+                        self.voices.note_on(
+                            note,
+                            velocity,
+                            util::midi_note_to_freq(note),
+                            max_polyphony,
+                        );
                     }
-                    NoteEvent::NoteOff { .. } => {
-                        self.adsr.off();
+                    NoteEvent::NoteOff { note, .. } => {
+                        self.voices.note_off(note);
                     }
                     _ => (),
                 }
@@ -161,15 +319,72 @@ impl Plugin for PawWave {
 
             // Get the smoothed volume
             let volume = self.params.volume.smoothed.next();
+            let waveform = self.params.waveform.value();
+            let cutoff = self.params.filter_cutoff.smoothed.next();
+            let resonance = self.params.filter_resonance.smoothed.next();
+            let filter_mode = self.params.filter_mode.value();
+            let pulse_width = self.params.pulse_width.smoothed.next();
+            let fm_ratio = self.params.fm_ratio.smoothed.next();
+            let fm_depth = self.params.fm_depth.smoothed.next();
+            let envelope_curve = self.params.envelope_curve.smoothed.next();
+            let lfo_target = self.params.lfo_target.value();
+            let lfo_rate = self.params.lfo_rate.smoothed.next();
+            let lfo_depth = self.params.lfo_depth.smoothed.next();
+
+            self.lfo.set_frequency(self.sample_rate, lfo_rate);
+            let lfo_sample = self.lfo.next_sample();
+
+            let vibrato_mult = if lfo_target == LfoTarget::Vibrato {
+                1.0 + lfo_depth * lfo_sample * 0.06
+            } else {
+                1.0
+            };
+            let tremolo_mult = if lfo_target == LfoTarget::Tremolo {
+                1.0 - lfo_depth * (1.0 - lfo_sample) * 0.5
+            } else {
+                1.0
+            };
+
+            // Sum all currently active voices within the allocatable range (the same range
+            // `note_on` picks from), scaling down as more of them stack up so the mix doesn't
+            // clip.
+            let num_active = self.voices.voices()[..max_polyphony]
+                .iter()
+                .filter(|voice| voice.is_active())
+                .count()
+                .max(1) as f32;
+
+            let mut mix = 0.0;
+            for voice in &mut self.voices.voices_mut()[..max_polyphony] {
+                if !voice.is_active() {
+                    continue;
+                }
 
-            // Compute the next ADSR value
-            self.gain
-                .set_target(self.sample_rate, self.adsr.next_sample());
+                voice.osc.set_frequency(voice.base_freq * vibrato_mult);
+                voice.modulator.set_frequency(voice.base_freq * fm_ratio);
+                let modulator_sample = voice.modulator.next_sample(OscillatorType::Sine);
+
+                voice.osc.set_pulse_width(pulse_width);
+                let osc_sample = voice
+                    .osc
+                    .next_sample_fm(waveform, fm_depth * modulator_sample);
+
+                voice.adsr.update_params(ADSRUpdate {
+                    curve: Some(envelope_curve),
+                    ..Default::default()
+                });
+                let filtered = voice
+                    .filter
+                    .process(osc_sample, cutoff, resonance, filter_mode);
+                mix += filtered * voice.adsr.next_sample();
+            }
+            mix /= num_active;
+            mix *= tremolo_mult;
+            mix *= volume;
 
             // Generate and process audio for all channels
             for sample in channel_samples {
-                *sample = self.osc.next_sample(self.params.waveform.value()) * self.gain.next();
-                *sample *= volume;
+                *sample = mix;
             }
         }
 
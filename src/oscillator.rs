@@ -1,11 +1,49 @@
 //! This module implements a PolyBLEP (Polynomial Bandlimited Step Function) oscillator.
 //! PolyBLEP is a technique used to reduce aliasing in digital oscillators, particularly
 //! for non-sinusoidal waveforms like square, saw, and triangle waves.
+//!
+//! It also builds a shared sine lookup table, used both as a cheaper alternative to `.sin()`
+//! for the sine waveform and as the building block for the [`crate::lfo`] module.
 
 use std::f32::consts::PI;
+use std::sync::OnceLock;
 
 use nih_plug::prelude::Enum;
 
+/// Number of entries in the sine lookup table, not counting the trailing guard entry.
+const TABLE_SIZE: usize = 512;
+
+/// Builds the sine lookup table, with a trailing guard entry equal to the first so that
+/// interpolation never has to special-case wraparound.
+fn init_sine_tab() -> [f32; TABLE_SIZE + 1] {
+    let mut table = [0.0; TABLE_SIZE + 1];
+    for (i, entry) in table.iter_mut().enumerate() {
+        *entry = (2.0 * PI * i as f32 / TABLE_SIZE as f32).sin();
+    }
+    table
+}
+
+/// Returns the shared, lazily-built sine lookup table.
+fn sine_table() -> &'static [f32; TABLE_SIZE + 1] {
+    static TABLE: OnceLock<[f32; TABLE_SIZE + 1]> = OnceLock::new();
+    TABLE.get_or_init(init_sine_tab)
+}
+
+/// Computes `sin(2 * PI * phase)` via a linearly-interpolated table lookup, for a measurable
+/// CPU win over calling `.sin()` on every sample.
+///
+/// # Arguments
+///
+/// * `phase` - The phase to sample at, in the range `[0.0, 1.0)`
+#[inline(always)]
+pub(crate) fn fast_sin(phase: f32) -> f32 {
+    let table = sine_table();
+    let index = phase * TABLE_SIZE as f32;
+    let i0 = (index as usize).min(TABLE_SIZE - 1);
+    let frac = index - i0 as f32;
+    table[i0] * (1.0 - frac) + table[i0 + 1] * frac
+}
+
 /// Represents different types of oscillator waveforms.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
 pub enum OscillatorType {
@@ -29,6 +67,8 @@ pub struct PolyBlepOscillator {
     phase: f32,
     /// The amount to increment the phase each sample.
     phase_increment: f32,
+    /// The duty cycle of the square wave (0.0 to 1.0), ignored by other waveforms.
+    pulse_width: f32,
 }
 
 impl PolyBlepOscillator {
@@ -49,11 +89,18 @@ impl PolyBlepOscillator {
             frequency,
             phase: 0.0,
             phase_increment: 0.0,
+            pulse_width: 0.5,
         };
         osc.set_frequency(frequency);
         osc
     }
 
+    /// Resets the oscillator's phase to zero, e.g. when its voice is retriggered.
+    #[inline(always)]
+    pub fn reset_phase(&mut self) {
+        self.phase = 0.0;
+    }
+
     /// Sets the frequency of the oscillator.
     ///
     /// # Arguments
@@ -65,6 +112,17 @@ impl PolyBlepOscillator {
         self.phase_increment = self.frequency / self.sample_rate;
     }
 
+    /// Sets the duty cycle used by the square wave, clamped to keep both edges of the pulse
+    /// away from the BLEP discontinuity at phase 0.0.
+    ///
+    /// # Arguments
+    ///
+    /// * `pulse_width` - The new duty cycle (0.0 to 1.0)
+    #[inline(always)]
+    pub fn set_pulse_width(&mut self, pulse_width: f32) {
+        self.pulse_width = pulse_width.clamp(0.01, 0.99);
+    }
+
     /// Applies the PolyBLEP correction to reduce aliasing at discontinuities.
     ///
     /// # Arguments
@@ -98,24 +156,48 @@ impl PolyBlepOscillator {
     /// The next sample value in the range [-1.0, 1.0]
     #[inline(always)]
     pub fn next_sample(&mut self, osc_type: OscillatorType) -> f32 {
+        self.next_sample_fm(osc_type, 0.0)
+    }
+
+    /// Generates the next sample of the oscillator, phase-modulated by another signal.
+    ///
+    /// This is the phase-modulation form of FM: the waveform is sampled at
+    /// `self.phase + phase_mod` instead of `self.phase`, which is numerically stable since it
+    /// never perturbs `phase_increment`. The PolyBLEP discontinuity corrections are still
+    /// computed from the unmodulated `self.phase`, so the anti-aliasing stays tied to the
+    /// carrier's own phase increment rather than the (possibly fast-moving) modulated phase.
+    ///
+    /// # Arguments
+    ///
+    /// * `osc_type` - The type of oscillator waveform to generate
+    /// * `phase_mod` - The phase offset to apply this sample, typically `depth * modulator_sample`
+    ///
+    /// # Returns
+    ///
+    /// The next sample value in the range [-1.0, 1.0]
+    #[inline(always)]
+    pub fn next_sample_fm(&mut self, osc_type: OscillatorType, phase_mod: f32) -> f32 {
+        let sample_phase = (self.phase + phase_mod).rem_euclid(1.0);
+
         let sample = match osc_type {
-            OscillatorType::Sine => (2.0 * PI * self.phase).sin(),
+            OscillatorType::Sine => fast_sin(sample_phase),
             OscillatorType::Square => {
-                let mut sample = if self.phase < 0.5 { 1.0 } else { -1.0 };
+                let pw = self.pulse_width;
+                let mut sample = if sample_phase < pw { 1.0 } else { -1.0 };
                 sample += self.poly_blep(self.phase);
-                sample -= self.poly_blep((self.phase + 0.5) % 1.0);
+                sample -= self.poly_blep((self.phase + (1.0 - pw)) % 1.0);
                 sample
             }
             OscillatorType::Saw => {
-                let mut sample = 2.0 * self.phase - 1.0;
+                let mut sample = 2.0 * sample_phase - 1.0;
                 sample -= self.poly_blep(self.phase);
                 sample
             }
             OscillatorType::Triangle => {
-                let mut sample = if self.phase < 0.5 {
-                    4.0 * self.phase - 1.0
+                let mut sample = if sample_phase < 0.5 {
+                    4.0 * sample_phase - 1.0
                 } else {
-                    3.0 - 4.0 * self.phase
+                    3.0 - 4.0 * sample_phase
                 };
                 let dt = self.phase_increment;
 
@@ -133,3 +215,23 @@ impl PolyBlepOscillator {
         sample.clamp(-1.0, 1.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fast_sin_matches_exact_sine() {
+        // The table is linearly interpolated, so it won't be bit-exact with `.sin()`, but it
+        // should track it closely everywhere, including right at the table's wraparound guard.
+        for i in 0..1000 {
+            let phase = i as f32 / 1000.0;
+            let exact = (2.0 * PI * phase).sin();
+            let fast = fast_sin(phase);
+            assert!(
+                (exact - fast).abs() < 1e-3,
+                "phase {phase}: exact {exact}, fast {fast}"
+            );
+        }
+    }
+}
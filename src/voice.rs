@@ -0,0 +1,116 @@
+//! This module implements per-note voice state and a simple fixed-size voice pool,
+//! letting the synthesizer play more than one note at a time.
+
+use crate::envelope::ADSR;
+use crate::filter::StateVariableFilter;
+use crate::oscillator::PolyBlepOscillator;
+
+/// The maximum number of notes that can sound simultaneously.
+pub const MAX_VOICES: usize = 16;
+
+/// A single synthesizer voice: an oscillator and envelope bound to one MIDI note.
+pub struct Voice {
+    /// The MIDI note number this voice was last triggered with, if any. It is kept after the
+    /// note has finished releasing.
+    pub note: Option<u8>,
+    /// The note's base frequency in Hz, used to derive the FM modulator's frequency.
+    pub base_freq: f32,
+    /// The voice's own oscillator (the FM carrier).
+    pub osc: PolyBlepOscillator,
+    /// The voice's own FM modulator oscillator.
+    pub modulator: PolyBlepOscillator,
+    /// The voice's own envelope.
+    pub adsr: ADSR,
+    /// The voice's own state-variable filter instance.
+    pub filter: StateVariableFilter,
+}
+
+impl Voice {
+    /// Creates a new, idle voice.
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            note: None,
+            base_freq: 440.0,
+            osc: PolyBlepOscillator::new(sample_rate, 440.0),
+            modulator: PolyBlepOscillator::new(sample_rate, 440.0),
+            adsr: ADSR::new(0.02, 0.02, 0.5, 0.5, sample_rate),
+            filter: StateVariableFilter::new(sample_rate),
+        }
+    }
+
+    /// Whether this voice is currently producing sound.
+    pub fn is_active(&self) -> bool {
+        self.note.is_some() && self.adsr.is_active()
+    }
+}
+
+/// A fixed-size pool of [`Voice`]s that allocates and steals voices for incoming MIDI notes.
+pub struct VoiceManager {
+    voices: Vec<Voice>,
+    /// Monotonically increasing counter used to find the oldest voice when stealing.
+    next_age: u64,
+    ages: Vec<u64>,
+}
+
+impl VoiceManager {
+    /// Creates a new voice manager with a pool of [`MAX_VOICES`] voices.
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            voices: (0..MAX_VOICES).map(|_| Voice::new(sample_rate)).collect(),
+            next_age: 0,
+            ages: vec![0; MAX_VOICES],
+        }
+    }
+
+    /// Re-creates the voice pool for a new sample rate, discarding any currently playing notes.
+    pub fn reset(&mut self, sample_rate: f32) {
+        *self = Self::new(sample_rate);
+    }
+
+    /// Returns the whole pool of [`MAX_VOICES`] voices; [`Self::note_on`] only allocates from
+    /// the first `max_polyphony` of them.
+    pub fn voices(&self) -> &[Voice] {
+        &self.voices
+    }
+
+    /// Returns the currently allocated voices for mutation.
+    pub fn voices_mut(&mut self) -> &mut [Voice] {
+        &mut self.voices
+    }
+
+    /// Triggers `note`, allocating a free voice or stealing the oldest one if the pool (limited
+    /// to `max_polyphony` voices) is full.
+    pub fn note_on(&mut self, note: u8, velocity: f32, freq: f32, max_polyphony: usize) {
+        let max_polyphony = max_polyphony.clamp(1, MAX_VOICES);
+
+        let index = self.voices[..max_polyphony]
+            .iter()
+            .position(|voice| !voice.is_active())
+            .unwrap_or_else(|| {
+                // No free voice: steal the oldest one.
+                (0..max_polyphony)
+                    .min_by_key(|&i| self.ages[i])
+                    .unwrap_or(0)
+            });
+
+        let voice = &mut self.voices[index];
+        voice.note = Some(note);
+        voice.base_freq = freq;
+        voice.osc.set_frequency(freq);
+        voice.osc.reset_phase();
+        voice.modulator.reset_phase();
+        voice.filter.reset();
+        voice.adsr.on(velocity);
+
+        self.ages[index] = self.next_age;
+        self.next_age += 1;
+    }
+
+    /// Releases the voice currently playing `note`, if any. Voices that were given `note`
+    /// earlier and are already releasing are left as they are.
+    pub fn note_off(&mut self, note: u8) {
+        for voice in self.voices.iter_mut().filter(|voice| voice.note == Some(note)) {
+            voice.adsr.off();
+        }
+    }
+}